@@ -7,87 +7,377 @@
 /// DISCLAIMER: The code presented here is a simple example to illustrate
 /// how to implement a pandas extension in Rust. It is not production code,
 /// since for simplicity instead of properly controlling possible errors,
-/// the code in most cases will panic. The code is also simplified to only
-/// work with pandas Series of a single data type (uint64). Also, there are
-/// simplifications on the prime number logic, like returning that the
-/// primality of zero, one or null is false.
+/// the code in most cases will panic. The code accepts any integer Series
+/// dtype, single- or multi-chunk. Also, there are simplifications on the
+/// prime number logic, like returning that the primality of zero, one or
+/// null is false.
 use pyo3::prelude::*;
-use pyo3::ffi::Py_uintptr_t;
-use arrow2::array::{UInt64Array, BooleanArray};
+use pyo3::types::PyCapsule;
+use arrow2::array::{Array, Int64Array, UInt64Array, BooleanArray};
+use arrow2::compute::cast::{cast, CastOptions};
 use arrow2::datatypes::{DataType, Field};
 use arrow2::bitmap::MutableBitmap;
 use arrow2::ffi;
-use libc::uintptr_t;
+use std::ffi::CString;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
+const ARROW_SCHEMA_CAPSULE_NAME: &str = "arrow_schema";
+const ARROW_ARRAY_CAPSULE_NAME: &str = "arrow_array";
+const ARROW_STREAM_CAPSULE_NAME: &str = "arrow_array_stream";
 
 /// Load the original Arrow array in pandas as a Rust Arrow2 array.
 ///
-/// This is done by calling the `_export_to_c` function in the C++
-/// implementation of Arrow (the implementation pandas uses) via FFI. The
-/// function will create a struct with the relevant information
-/// of the data (the memory address, the schema...). The data itself
-/// is not copied, we access the original data. Only the struct with
-/// the metadata is allocated.
+/// This is done through the Arrow PyCapsule Protocol: we call
+/// `__arrow_c_array__()` on the incoming object, which must return a
+/// 2-tuple of PyCapsules named `"arrow_schema"` and `"arrow_array"`
+/// wrapping the raw `ArrowSchema`/`ArrowArray` C structs, and import those
+/// via arrow2's FFI support. For `UInt64` input the data itself is not
+/// copied, we access the original data; other integer types are copied
+/// into a new buffer when cast. This works with any protocol-compliant producer
+/// (pyarrow, polars, datafusion...), rather than tying us to the
+/// pyarrow-private `_export_to_c` this used to rely on.
+///
+/// Any integer width is accepted: `UInt64` arrays are used as-is, while
+/// every other integer type is cast to `UInt64` via `to_uint64` below, so
+/// pandas nullable int8/16/32/64 and uint8/16/32 columns all work.
 pub fn pyarrow_to_arrow2(pyarrow_array: &PyAny) -> UInt64Array {
-    let ffi_array = Box::new(ffi::ArrowArray::empty());
-    let array_ptr = &*ffi_array as *const ffi::ArrowArray;
+    let capsules = pyarrow_array
+        .call_method0("__arrow_c_array__")
+        .expect("object does not implement the Arrow PyCapsule Protocol (__arrow_c_array__)");
+    let (schema_capsule, array_capsule): (&PyCapsule, &PyCapsule) = capsules
+        .extract()
+        .expect("__arrow_c_array__ must return a (schema, array) tuple of PyCapsule objects");
+
+    assert_eq!(
+        schema_capsule.name().unwrap().map(|name| name.to_str().unwrap()),
+        Some(ARROW_SCHEMA_CAPSULE_NAME),
+    );
+    assert_eq!(
+        array_capsule.name().unwrap().map(|name| name.to_str().unwrap()),
+        Some(ARROW_ARRAY_CAPSULE_NAME),
+    );
 
-    let ffi_schema = Box::new(ffi::ArrowSchema::empty());
-    let schema_ptr = &*ffi_schema as *const ffi::ArrowSchema;
+    unsafe {
+        let schema_ptr = schema_capsule.pointer() as *mut ffi::ArrowSchema;
+        let array_ptr = array_capsule.pointer() as *mut ffi::ArrowArray;
 
-    pyarrow_array.call_method1(
-        "_export_to_c",
-        (array_ptr as Py_uintptr_t, schema_ptr as Py_uintptr_t),
-    ).unwrap();
+        let field = ffi::import_field_from_c(&*schema_ptr).unwrap();
+        // Move the ArrowArray out of the capsule and leave an empty
+        // (already-released) one in its place, so the capsule's own
+        // destructor becomes a no-op instead of releasing it a second time
+        // after arrow2 has taken ownership.
+        let raw_array = std::ptr::replace(array_ptr, ffi::ArrowArray::empty());
+        let array = ffi::import_array_from_c(raw_array, field.data_type).unwrap();
+        array_to_uint64(array)
+    }
+}
+
+/// Import a chunked/streaming Arrow source through the Arrow C Stream
+/// interface (`__arrow_c_stream__`), yielding one `UInt64Array` per chunk.
+///
+/// This lets large, multi-chunk pandas/Arrow columns (`ChunkedArray`) be
+/// processed one array at a time instead of forcing the caller to combine
+/// all chunks into a single contiguous array first. We hand the imported
+/// `ArrowArrayStream` to arrow2's own `ArrowArrayStreamReader`, which pulls
+/// the schema once and then one array per `next()` call, and releases the
+/// stream (schema included) when the reader is dropped.
+pub fn pyarrow_to_arrow2_stream(pyarrow_stream: &PyAny) -> Vec<UInt64Array> {
+    let stream_capsule = pyarrow_stream
+        .call_method0("__arrow_c_stream__")
+        .expect("object does not implement the Arrow C Stream interface (__arrow_c_stream__)");
+    let stream_capsule: &PyCapsule = stream_capsule
+        .extract()
+        .expect("__arrow_c_stream__ must return a single PyCapsule");
+    assert_eq!(
+        stream_capsule.name().unwrap().map(|name| name.to_str().unwrap()),
+        Some(ARROW_STREAM_CAPSULE_NAME),
+    );
 
     unsafe {
-        let field = ffi::import_field_from_c(ffi_schema.as_ref()).unwrap();
-        let array = ffi::import_array_from_c(*ffi_array, field.data_type).unwrap();
+        let stream_ptr = stream_capsule.pointer() as *mut ffi::ArrowArrayStream;
+        // Move the ArrowArrayStream out of the capsule and leave an empty
+        // (already-released) one in its place, so the capsule's own
+        // destructor becomes a no-op once the reader below takes ownership.
+        let raw_stream = std::ptr::replace(stream_ptr, ffi::ArrowArrayStream::empty());
+
+        let mut reader = ffi::ArrowArrayStreamReader::try_new(Box::new(raw_stream))
+            .expect("invalid Arrow C Stream");
 
-        if *array.data_type() != DataType::UInt64 {
-            panic!("array type must be uint64");
+        let mut chunks = Vec::new();
+        while let Some(array) = reader.next() {
+            let array = array.expect("error while pulling the next chunk from the Arrow C Stream");
+            chunks.push(array_to_uint64(array));
         }
-        array.as_any().downcast_ref::<UInt64Array>().unwrap().clone()
+        chunks
     }
 }
 
-pub fn arrow2_to_pyarrow(arrow2_array: BooleanArray, py: Python) -> PyResult<PyObject> {
-    let pyarrow_mod = py.import("pyarrow")?;
+/// Normalize an imported Arrow array of any supported integer width to a
+/// `UInt64Array`, so both the single-array and streaming import paths
+/// share the same dispatch logic.
+fn array_to_uint64(array: Box<dyn Array>) -> UInt64Array {
+    match array.data_type() {
+        DataType::UInt64 => array.as_any().downcast_ref::<UInt64Array>().unwrap().clone(),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+        | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => to_uint64(array.as_ref()),
+        other => panic!("unsupported array type for is_prime: {:?}", other),
+    }
+}
 
-    let arrow2_field = Field::new("is_prime", DataType::Boolean, false);
+/// Cast an integer array of any supported width to `UInt64`.
+///
+/// The array is first cast to `Int64`, wide enough to hold every supported
+/// input type without loss, and then narrowed to `UInt64`, treating
+/// negative values as non-prime (mapped to `0`) rather than wrapping
+/// around, since `is_prime_scalar` already returns `false` for `0`.
+fn to_uint64(array: &dyn Array) -> UInt64Array {
+    let int64_array = cast(array, &DataType::Int64, CastOptions::default())
+        .expect("could not cast array to a supported integer type");
+    let int64_array = int64_array.as_any().downcast_ref::<Int64Array>().unwrap();
+
+    let values: Vec<u64> = int64_array
+        .values()
+        .iter()
+        .map(|&n| if n < 0 { 0 } else { n as u64 })
+        .collect();
+    UInt64Array::new(DataType::UInt64, values.into(), int64_array.validity().cloned())
+}
 
-    let pyarrow_field = Box::new(ffi::export_field_to_c(&arrow2_field));
-    let pyarrow_array = Box::new(ffi::export_array_to_c(arrow2_array.boxed()));    
-    
-    let schema_ptr: *const ffi::ArrowSchema = &*pyarrow_field;    
-    let array_ptr: *const ffi::ArrowArray = &*pyarrow_array;
+/// Import either a single Arrow array or a chunked Arrow stream, depending
+/// on which protocol the incoming object implements, normalizing both into
+/// a list of `UInt64Array` chunks.
+fn import_chunks(pyarrow_obj: &PyAny) -> Vec<UInt64Array> {
+    if pyarrow_obj.hasattr("__arrow_c_stream__").unwrap() {
+        pyarrow_to_arrow2_stream(pyarrow_obj)
+    } else {
+        vec![pyarrow_to_arrow2(pyarrow_obj)]
+    }
+}
 
-    let pyarrow_array = pyarrow_mod.getattr("Array")?
-                                   .call_method1("_import_from_c",
-                                                 (array_ptr as uintptr_t,
-                                                  schema_ptr as uintptr_t))?;
-    Ok(pyarrow_array.to_object(py))
+/// Minimal wrapper exposing a computed arrow2 array to Python through the
+/// Arrow PyCapsule Protocol.
+///
+/// Returning this object (instead of building a `pyarrow.Array` through
+/// pyarrow's private `_import_from_c`) lets any protocol-compliant
+/// consumer (pyarrow, pandas, polars...) pick up the result without this
+/// extension depending on pyarrow internals.
+#[pyclass]
+pub struct Arrow2Array {
+    array: BooleanArray,
+}
+
+#[pymethods]
+impl Arrow2Array {
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        _requested_schema: Option<&PyAny>,
+    ) -> PyResult<(&'py PyCapsule, &'py PyCapsule)> {
+        let array_capsule = PyCapsule::new(
+            py,
+            Sendable(ffi::export_array_to_c(self.array.clone().boxed())),
+            Some(CString::new(ARROW_ARRAY_CAPSULE_NAME).unwrap()),
+        )?;
+        Ok((export_field_capsule(py, self.array.data_type())?, array_capsule))
+    }
+
+    fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyCapsule> {
+        export_field_capsule(py, self.array.data_type())
+    }
+}
+
+/// `ArrowSchema`/`ArrowArray` hold raw pointers, so they are not `Send` on
+/// their own, but `PyCapsule::new` requires its value to be. We only ever
+/// touch them while holding the GIL, so wrapping them to assert `Send` is
+/// safe here, and lets their `Drop` impl (which calls the C `release`
+/// callback) run normally when the capsule is destroyed, without us having
+/// to call `release` by hand.
+#[repr(transparent)]
+struct Sendable<T>(T);
+unsafe impl<T> Send for Sendable<T> {}
+
+/// Build an `"arrow_schema"` PyCapsule for the given arrow2 data type.
+fn export_field_capsule<'py>(py: Python<'py>, data_type: &DataType) -> PyResult<&'py PyCapsule> {
+    let field = Field::new("is_prime", data_type.clone(), true);
+    PyCapsule::new(
+        py,
+        Sendable(ffi::export_field_to_c(&field)),
+        Some(CString::new(ARROW_SCHEMA_CAPSULE_NAME).unwrap()),
+    )
+}
+
+/// Wrap a computed `BooleanArray` so it can be returned to Python through
+/// the Arrow PyCapsule Protocol.
+pub fn arrow2_to_pyarrow(arrow2_array: BooleanArray, py: Python) -> PyResult<PyObject> {
+    Py::new(py, Arrow2Array { array: arrow2_array })?.extract(py)
 }
 
 /// Return whether an integer number is prime or not.
 ///
-/// This is one of the common implementations to test primality.
-/// Not the fastest implementation, but an efficient one.
+/// This uses a deterministic Miller-Rabin test with the witness set
+/// `{2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37}`, which is proven correct
+/// for every `n < 2^64`. Unlike trial division, this stays fast even for
+/// values close to `u64::MAX`.
 ///
 /// Primality of 0 and 1 is undefined, but for simplicity we
 /// return true for prime numbers, and false for everything else.
 pub fn is_prime_scalar(n: u64) -> bool {
-    if n == 0 || n == 1 { return false }
-    if n == 2 || n == 3 { return true }
-    if n % 2 == 0 || n % 3 == 0 { return false }
-
-    let limit = (n as f64).powf(0.5).ceil() as u64;
-    for i in (5..limit).step_by(6) {
-        if n % i == 0 || n % (i + 2) == 0 {
-            return false;
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 { return false }
+    for &p in WITNESSES.iter() {
+        if n == p { return true }
+    }
+    if n.is_multiple_of(2) { return false }
+
+    // Write n - 1 = 2^r * d with d odd.
+    let mut d = n - 1;
+    let mut r = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        r += 1;
+    }
+
+    'witness: for &a in WITNESSES.iter() {
+        if a % n == 0 { continue }
+
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 { continue }
+
+        for _ in 1..r {
+            x = mul_mod(x, x, n);
+            if x == n - 1 { continue 'witness }
+        }
+        return false;
+    }
+    true
+}
+
+/// Compute `(base^exponent) mod modulus`, using `u128` intermediates in
+/// `mul_mod` so the squaring involved never overflows `u64`.
+fn pow_mod(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1u64;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
         }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
     }
-    return true;
+    result
+}
+
+/// Compute `(a * b) mod modulus` without overflowing, by widening the
+/// multiplication to `u128`.
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+/// Largest maximum value for which `is_prime` builds a sieve instead of
+/// testing each element independently with Miller-Rabin.
+const SIEVE_MAX_THRESHOLD: u64 = 100_000_000;
+
+/// Largest number of units of `max` per element for which the sieve is
+/// still built: it needs at least one element per `SIEVE_MIN_DENSITY`
+/// units of `max`. A sieve over `0..=max` costs `O(max)`; that's only
+/// amortized if there are enough elements relying on it, so an array of a
+/// handful of values below a huge `max` (e.g. one element close to
+/// `SIEVE_MAX_THRESHOLD`) must fall back to per-element Miller-Rabin
+/// instead of allocating and filling a ~100MB bitmap for a single lookup.
+const SIEVE_MIN_DENSITY: u64 = 50;
+
+/// Build a Sieve of Eratosthenes over `0..=max`, returning a bitmap where
+/// position `n` is `true` when `n` is prime.
+///
+/// This is only worth it when many elements share a moderate upper bound,
+/// since the sieve answers each of them with a single lookup afterwards.
+fn sieve_of_eratosthenes(max: u64) -> Vec<bool> {
+    let max = max as usize;
+    let mut is_prime = vec![true; max + 1];
+    is_prime[0] = false;
+    if max >= 1 {
+        is_prime[1] = false;
+    }
+
+    let mut p = 2usize;
+    while p * p <= max {
+        if is_prime[p] {
+            let mut multiple = p * p;
+            while multiple <= max {
+                is_prime[multiple] = false;
+                multiple += p;
+            }
+        }
+        p += 1;
+    }
+    is_prime
+}
+
+/// Below this many elements, the overhead of spinning up rayon's thread
+/// pool outweighs the benefit of parallelizing, so the serial path is
+/// used instead.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 10_000;
+
+/// Evaluate primality for every element of `chunk`, using `sieve` for an
+/// O(1) lookup when available and falling back to `is_prime_scalar`
+/// otherwise. Null elements are reported as non-prime.
+///
+/// With the `parallel` feature enabled, arrays with at least
+/// `PARALLEL_THRESHOLD` elements are evaluated by splitting the values
+/// slice across rayon's thread pool instead of a single serial loop.
+fn primality_bitmap(chunk: &UInt64Array, sieve: &Option<Vec<bool>>) -> Vec<bool> {
+    // The sieve is sized from the maximum *valid* value (see `is_prime`),
+    // but `chunk.values()` also contains the undefined physical values
+    // backing null slots, which may exceed that bound. Use a bounds-checked
+    // lookup instead of indexing directly so those slots can't panic; they
+    // are zeroed out below regardless of what this returns for them.
+    let lookup = |number: u64| match sieve {
+        Some(sieve) => sieve.get(number as usize).copied().unwrap_or(false),
+        None => is_prime_scalar(number),
+    };
+
+    #[cfg(feature = "parallel")]
+    let mut result: Vec<bool> = if chunk.len() >= PARALLEL_THRESHOLD {
+        chunk.values().as_slice().par_iter().map(|&number| lookup(number)).collect()
+    } else {
+        chunk.values().iter().map(|&number| lookup(number)).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let mut result: Vec<bool> = chunk.values().iter().map(|&number| lookup(number)).collect();
+
+    if let Some(validity) = chunk.validity() {
+        for (is_valid, prime) in validity.iter().zip(result.iter_mut()) {
+            if !is_valid {
+                *prime = false;
+            }
+        }
+    }
+    result
+}
+
+/// Whether `chunk` contains at least one non-prime element (nulls don't
+/// count as non-prime, they are simply skipped).
+///
+/// With the `parallel` feature enabled, arrays with at least
+/// `PARALLEL_THRESHOLD` elements use rayon's `any`, which is a
+/// short-circuiting parallel reduction: it stops dispatching new work as
+/// soon as any thread finds a non-prime, rather than testing every
+/// element.
+fn has_non_prime(chunk: &UInt64Array) -> bool {
+    let is_non_prime = |(i, &number): (usize, &u64)| {
+        chunk.validity().is_none_or(|validity| validity.get_bit(i)) && !is_prime_scalar(number)
+    };
+
+    #[cfg(feature = "parallel")]
+    if chunk.len() >= PARALLEL_THRESHOLD {
+        return chunk.values().as_slice().par_iter().enumerate().any(is_non_prime);
+    }
+
+    chunk.values().iter().enumerate().any(is_non_prime)
 }
 
 /// Check if every element of an array is prime.
@@ -97,16 +387,33 @@ pub fn is_prime_scalar(n: u64) -> bool {
 ///
 /// This is the function that will be made accessible from Python,
 /// so the input is a PyAny, that we expect to be a PyArrow
-/// array of int64.
+/// array of int64. It is also accepted to be a chunked/streaming Arrow
+/// source implementing `__arrow_c_stream__`, in which case each chunk is
+/// processed in turn and the resulting bitmaps concatenated.
+///
+/// When every value is at most `SIEVE_MAX_THRESHOLD` and there are enough
+/// elements to make it worthwhile, a sieve is built once for the whole
+/// input and each element is answered with an O(1) lookup, instead of
+/// running Miller-Rabin per element.
 #[pyfunction]
 fn is_prime(raw_pyarrow_array: &PyAny, py: Python) -> PyResult<PyObject> {
-    let mut bitmap = MutableBitmap::with_capacity(raw_pyarrow_array.len().unwrap());
+    let chunks = import_chunks(raw_pyarrow_array);
+
+    let max_value = chunks
+        .iter()
+        .flat_map(|chunk| chunk.iter())
+        .flatten()
+        .copied()
+        .max();
+    let len: u64 = chunks.iter().map(|chunk| chunk.len() as u64).sum();
+    let sieve = max_value
+        .filter(|&max| max <= SIEVE_MAX_THRESHOLD && len.saturating_mul(SIEVE_MIN_DENSITY) >= max)
+        .map(sieve_of_eratosthenes);
 
-    for array_element in pyarrow_to_arrow2(raw_pyarrow_array).iter() {
-        if let Some(&number) = array_element {
-            bitmap.push(is_prime_scalar(number));
-        } else {
-            bitmap.push(false);
+    let mut bitmap = MutableBitmap::new();
+    for chunk in &chunks {
+        for prime in primality_bitmap(chunk, &sieve) {
+            bitmap.push(prime);
         }
     }
     let result = BooleanArray::new(DataType::Boolean, bitmap.into(), None);
@@ -124,11 +431,15 @@ fn is_prime(raw_pyarrow_array: &PyAny, py: Python) -> PyResult<PyObject> {
 ///
 /// It is implemented mostly to illustrate extension arrays that return
 /// both a pandas Series or a Python scalar.
+///
+/// Like `is_prime`, a chunked/streaming Arrow source is also accepted, and
+/// the search still stops at the first non-prime found, across chunks
+/// (and, with the `parallel` feature, within a chunk too).
 #[pyfunction]
 fn are_all_primes(raw_pyarrow_array: &PyAny) -> PyResult<bool> {
-    for array_element in pyarrow_to_arrow2(&raw_pyarrow_array).iter() {
-        if let Some(number) = array_element {
-            if !is_prime_scalar(*number) { return Ok(false) }
+    for chunk in import_chunks(raw_pyarrow_array) {
+        if has_non_prime(&chunk) {
+            return Ok(false)
         }
     }
     Ok(true)
@@ -137,7 +448,64 @@ fn are_all_primes(raw_pyarrow_array: &PyAny) -> PyResult<bool> {
 /// Python module that will be made available form Rust.
 #[pymodule]
 fn arrow_prime(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Arrow2Array>()?;
     m.add_function(wrap_pyfunction!(is_prime, m)?)?;
     m.add_function(wrap_pyfunction!(are_all_primes, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_prime_scalar_small_primes_and_composites() {
+        for &p in &[2u64, 3, 5, 7, 11, 13, 17, 97, 7919] {
+            assert!(is_prime_scalar(p), "{p} should be prime");
+        }
+        for &c in &[0u64, 1, 4, 6, 8, 9, 15, 100, 7921] {
+            assert!(!is_prime_scalar(c), "{c} should not be prime");
+        }
+    }
+
+    #[test]
+    fn is_prime_scalar_rejects_even_numbers() {
+        for n in (4u64..100).step_by(2) {
+            assert!(!is_prime_scalar(n), "{n} is even and should not be prime");
+        }
+    }
+
+    #[test]
+    fn is_prime_scalar_rejects_carmichael_number() {
+        // 561 = 3 * 11 * 17 is the smallest Carmichael number: composite,
+        // yet it passes a plain Fermat test for every base coprime to it.
+        // Deterministic Miller-Rabin must still reject it.
+        assert!(!is_prime_scalar(561));
+    }
+
+    #[test]
+    fn is_prime_scalar_handles_values_near_u64_max() {
+        // u64::MAX = 2^64 - 1 = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417, composite.
+        assert!(!is_prime_scalar(u64::MAX));
+        // Largest known prime below 2^64.
+        assert!(is_prime_scalar(u64::MAX - 58));
+    }
+
+    #[test]
+    fn sieve_of_eratosthenes_agrees_with_is_prime_scalar() {
+        let sieve = sieve_of_eratosthenes(10_000);
+        for n in 0..=10_000u64 {
+            assert_eq!(
+                sieve[n as usize],
+                is_prime_scalar(n),
+                "sieve disagrees with Miller-Rabin at {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn sieve_of_eratosthenes_handles_tiny_max() {
+        assert_eq!(sieve_of_eratosthenes(0), vec![false]);
+        assert_eq!(sieve_of_eratosthenes(1), vec![false, false]);
+    }
+}